@@ -0,0 +1,206 @@
+// Copyright (c) 2025 Merge Layers Inc.
+//
+// This source code is licensed under the Business Source License 1.1
+// (the "License"); you may not use this file except in compliance with the
+// License. You may obtain a copy of the License at
+//
+//     https://github.com/malda-protocol/malda-zk-coprocessor/blob/main/LICENSE-BSL
+//
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+//
+//! Builds the zkVM guest program(s) and pins their image IDs against a
+//! committed lock file so a silent change to the verifying key (guest
+//! source drift, toolchain drift) fails the build instead of surfacing as a
+//! runtime surprise for downstream verifiers.
+
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use risc0_build::embed_methods;
+
+// `write_guest_registry`/`write_compressed_elfs` below emit
+// `crate::build_support::...` calls into generated code that's `include!`d
+// into lib.rs, so they resolve against *that* crate's `mod build_support;`
+// — this build script has no need of `src/build_support.rs` itself.
+#[path = "build_support_lock.rs"]
+mod build_support_lock;
+use build_support_lock::{image_id_hex, parse_lock, render_lock, symbol_prefix};
+
+/// Cargo sets `CARGO_FEATURE_<NAME>` for every enabled feature; this is how
+/// a build script reads a feature flag without a runtime `cfg!` check.
+const COMPRESSED_ELF_FEATURE_ENV: &str = "CARGO_FEATURE_COMPRESSED_ELF";
+
+/// Lock file, committed to the repo, mapping guest method name -> expected
+/// image ID (hex-encoded). Regenerate deliberately with
+/// `MALDA_UPDATE_IMAGE_ID_LOCK=1 cargo build`.
+const LOCK_FILE_NAME: &str = "methods.lock";
+
+/// Set to regenerate `methods.lock` instead of failing on a mismatch.
+const UPDATE_LOCK_ENV: &str = "MALDA_UPDATE_IMAGE_ID_LOCK";
+
+fn main() {
+    let guests = embed_methods();
+    check_image_id_lock(&guests);
+}
+
+fn lock_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(LOCK_FILE_NAME)
+}
+
+fn check_image_id_lock(guests: &[risc0_build::GuestListEntry]) {
+    let path = lock_path();
+    let mut locked = load_lock(&path);
+    let update = env::var(UPDATE_LOCK_ENV).is_ok();
+
+    let mut mismatches = Vec::new();
+    for guest in guests {
+        let digest = image_id_hex(guest.image_id);
+        match locked.get(guest.name.as_ref()) {
+            Some(expected) if *expected == digest => {}
+            Some(expected) => mismatches.push(format!(
+                "  {}: locked {expected} != computed {digest}",
+                guest.name
+            )),
+            None => mismatches.push(format!(
+                "  {}: missing from {LOCK_FILE_NAME} (computed {digest})",
+                guest.name
+            )),
+        }
+        locked.insert(guest.name.to_string(), digest);
+    }
+
+    if update {
+        write_lock(&path, &locked);
+        println!("cargo:warning=regenerated {LOCK_FILE_NAME}");
+    } else if !mismatches.is_empty() {
+        panic!(
+            "guest image ID drift detected against {LOCK_FILE_NAME}:\n{}\n\n\
+             If this drift is intentional (guest source or toolchain change), \
+             rerun with {UPDATE_LOCK_ENV}=1 to update the lock.",
+            mismatches.join("\n")
+        );
+    }
+
+    write_expected_image_id_consts(guests);
+    write_guest_registry(guests);
+    if env::var(COMPRESSED_ELF_FEATURE_ENV).is_ok() {
+        write_compressed_elfs(guests);
+    }
+}
+
+fn load_lock(path: &Path) -> BTreeMap<String, String> {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return BTreeMap::new();
+    };
+    parse_lock(&contents)
+}
+
+fn write_lock(path: &Path, locked: &BTreeMap<String, String>) {
+    fs::write(path, render_lock(locked)).expect("failed to write methods.lock");
+}
+
+/// Emits `pub const EXPECTED_IMAGE_ID_<NAME>: [u32; 8]` for each guest, so
+/// host code can assert the running build matches the pinned digest without
+/// re-deriving it from `methods.rs`. The words are the guest's computed image
+/// ID, written only after `check_image_id_lock` has found it equal to the
+/// `methods.lock` entry (or has rewritten the lock with it).
+fn write_expected_image_id_consts(guests: &[risc0_build::GuestListEntry]) {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let mut out = String::new();
+    for guest in guests {
+        let const_name = format!("EXPECTED_IMAGE_ID_{}", symbol_prefix(&guest.name));
+        let words = guest
+            .image_id
+            .iter()
+            .map(|word| format!("{word}u32"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        out.push_str(&format!(
+            "pub const {const_name}: [u32; 8] = [{words}];\n"
+        ));
+    }
+    fs::write(out_dir.join("methods_lock.rs"), out).expect("failed to write methods_lock.rs");
+}
+
+/// Emits a `GUESTS` registry and a `guest_by_name` lookup so host code can
+/// select a guest by string name at runtime instead of hardcoding one
+/// method's `*_ID`/`*_ELF` constants at compile time.
+fn write_guest_registry(guests: &[risc0_build::GuestListEntry]) {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let mut entries = String::new();
+    for guest in guests {
+        let symbol_prefix = symbol_prefix(&guest.name);
+        entries.push_str(&format!(
+            "    (\"{}\", {symbol_prefix}_ID, {symbol_prefix}_ELF),\n",
+            guest.name
+        ));
+    }
+
+    let out = format!(
+        "/// `(name, image_id, elf)` for every guest embedded in this build.\n\
+         pub static GUESTS: &[(&str, [u32; 8], &[u8])] = &[\n{entries}];\n\n\
+         /// Looks up a guest's `(image_id, elf)` by its method name.\n\
+         pub fn guest_by_name(name: &str) -> Option<([u32; 8], &'static [u8])> {{\n\
+         \u{20}   crate::build_support::lookup_guest(GUESTS, name)\n\
+         }}\n"
+    );
+    fs::write(out_dir.join("methods_registry.rs"), out).expect("failed to write methods_registry.rs");
+}
+
+/// Under the `compressed-elf` feature, stores each guest's ELF zstd-compressed
+/// alongside the existing uncompressed `*_ELF` const, so downstream binaries
+/// that rarely invoke proving can opt into shipping the smaller artifact.
+fn write_compressed_elfs(guests: &[risc0_build::GuestListEntry]) {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let mut out = String::new();
+    out.push_str("use std::sync::OnceLock;\n\n");
+
+    for guest in guests {
+        let symbol_prefix = symbol_prefix(&guest.name);
+        let elf_bytes = guest.elf.as_ref();
+        let compressed = zstd::stream::encode_all(elf_bytes, 19)
+            .expect("failed to zstd-compress guest ELF");
+        // `compressed-elf` gates whether zstd is an optional dependency for
+        // the *library* build (see build_support::decompress_elf), but this
+        // build script always links zstd to compress here, so the round-trip
+        // check below decompresses directly rather than through that helper.
+        let round_tripped = zstd::stream::decode_all(compressed.as_slice())
+            .expect("just-compressed bytes are valid zstd");
+        assert_eq!(
+            round_tripped, elf_bytes,
+            "zstd round-trip mismatch for guest {:?}",
+            guest.name
+        );
+
+        // Write the compressed bytes to their own file and pull them in with
+        // `include_bytes!` rather than a literal array in the generated
+        // source — rustc just mmaps the file instead of tokenizing a
+        // multi-hundred-KB comma-separated byte list.
+        let compressed_path = out_dir.join(format!("{}.elf.zst", guest.name));
+        fs::write(&compressed_path, &compressed).unwrap_or_else(|err| {
+            panic!("failed to write compressed ELF for guest {:?}: {err}", guest.name)
+        });
+
+        out.push_str(&format!(
+            "static {symbol_prefix}_ELF_ZSTD: &[u8] = include_bytes!({compressed_path:?});\n\
+             static {symbol_prefix}_ELF_CACHE: OnceLock<Vec<u8>> = OnceLock::new();\n\n\
+             /// Decompresses `{symbol_prefix}_ELF_ZSTD` on first call and returns the\n\
+             /// cached bytes on every call after that. Prefer this over the raw\n\
+             /// `{symbol_prefix}_ELF` const when the `compressed-elf` feature is on.\n\
+             pub fn {}_elf() -> &'static [u8] {{\n\
+             \u{20}   {symbol_prefix}_ELF_CACHE\n\
+             \u{20}       .get_or_init(|| crate::build_support::decompress_elf({symbol_prefix}_ELF_ZSTD))\n\
+             \u{20}       .as_slice()\n\
+             }}\n\n",
+            guest.name.to_lowercase().replace('-', "_"),
+        ));
+    }
+
+    fs::write(out_dir.join("methods_compressed.rs"), out)
+        .expect("failed to write methods_compressed.rs");
+}
@@ -0,0 +1,109 @@
+// Copyright (c) 2025 Merge Layers Inc.
+//
+// This source code is licensed under the Business Source License 1.1
+// (the "License"); you may not use this file except in compliance with the
+// License. You may obtain a copy of the License at
+//
+//     https://github.com/malda-protocol/malda-zk-coprocessor/blob/main/LICENSE-BSL
+//
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+//
+//! Pure helper logic for `build.rs`'s image-ID lock file, pulled out so it
+//! can be unit-tested without a real guest crate to build against (`build.rs`
+//! itself only runs as part of a full build).
+//!
+//! Unlike `src/build_support.rs`, nothing here is called from the published
+//! library at runtime, so this file is deliberately *not* `mod`-included
+//! into `lib.rs` — doing so left every function here flagged `dead_code` on
+//! a normal library build. `build.rs` pulls it in with `#[path]`, and
+//! `tests/build_rs_logic.rs` does the same so these unit tests still run
+//! under a normal `cargo test`.
+
+use std::collections::BTreeMap;
+
+/// Identifier prefix risc0-build derives a guest's generated constants from
+/// (e.g. `FOO_ID`/`FOO_ELF` for a guest named `foo`).
+pub(crate) fn symbol_prefix(guest_name: &str) -> String {
+    guest_name.to_uppercase().replace('-', "_")
+}
+
+pub(crate) fn image_id_hex(image_id: [u32; 8]) -> String {
+    image_id.iter().map(|word| format!("{word:08x}")).collect()
+}
+
+const LOCK_HEADER: &str = "\
+# Generated by methods/build.rs. Do not hand-edit.
+# Format: <guest method name> = \"<hex-encoded image ID>\"
+# Regenerate deliberately with: MALDA_UPDATE_IMAGE_ID_LOCK=1 cargo build -p methods
+
+";
+
+/// Parses a `methods.lock` TOML document into a method-name -> digest map.
+/// An empty or missing file parses as an empty map; TOML's own comment
+/// syntax (`#`) means the header above is never mistaken for data.
+pub(crate) fn parse_lock(contents: &str) -> BTreeMap<String, String> {
+    if contents.trim().is_empty() {
+        return BTreeMap::new();
+    }
+    toml::from_str(contents).expect("methods.lock is not valid TOML")
+}
+
+/// Renders a method-name -> digest map back into a `methods.lock` document.
+/// The header is re-emitted verbatim rather than round-tripped through the
+/// data map, so regenerating the lock can never drop or corrupt it.
+pub(crate) fn render_lock(locked: &BTreeMap<String, String>) -> String {
+    let mut out = LOCK_HEADER.to_string();
+    out.push_str(&toml::to_string(locked).expect("failed to serialize methods.lock"));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbol_prefix_replaces_hyphens_and_uppercases() {
+        assert_eq!(symbol_prefix("malda-guest"), "MALDA_GUEST");
+        assert_eq!(symbol_prefix("guest"), "GUEST");
+    }
+
+    #[test]
+    fn image_id_hex_is_zero_padded_lowercase_hex() {
+        let digest = image_id_hex([1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(
+            digest,
+            "00000001000000020000000300000004000000050000000600000007\
+             00000008"
+        );
+        assert_eq!(digest.len(), 64);
+        assert_eq!(
+            image_id_hex([0xdeadbeef, 0xa, 0, 0, 0, 0, 0, 0]),
+            format!("deadbeef0000000a{}", "0".repeat(48))
+        );
+    }
+
+    #[test]
+    fn parse_lock_round_trips_through_render_lock() {
+        let mut locked = BTreeMap::new();
+        locked.insert("guest".to_string(), "00".repeat(32));
+        locked.insert("malda-guest".to_string(), "11".repeat(32));
+
+        let rendered = render_lock(&locked);
+        assert!(rendered.starts_with("# Generated by methods/build.rs. Do not hand-edit."));
+
+        assert_eq!(parse_lock(&rendered), locked);
+    }
+
+    #[test]
+    fn parse_lock_on_header_only_file_is_empty() {
+        let header_only = render_lock(&BTreeMap::new());
+        assert!(parse_lock(&header_only).is_empty());
+    }
+
+    #[test]
+    fn parse_lock_on_missing_file_contents_is_empty() {
+        assert!(parse_lock("").is_empty());
+    }
+}
@@ -0,0 +1,70 @@
+// Copyright (c) 2025 Merge Layers Inc.
+//
+// This source code is licensed under the Business Source License 1.1
+// (the "License"); you may not use this file except in compliance with the
+// License. You may obtain a copy of the License at
+//
+//     https://github.com/malda-protocol/malda-zk-coprocessor/blob/main/LICENSE-BSL
+//
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+//
+//! Helper logic called from the code that `build.rs` generates into this
+//! crate, limited to what the library itself calls at runtime (`lookup_guest` via
+//! the generated `guest_by_name`, `decompress_elf` via the generated
+//! `<name>_elf()` accessors under the `compressed-elf` feature). Build-only
+//! logic with no runtime caller (lock-file parsing, symbol-prefix
+//! derivation) lives in `build_support_lock.rs` instead, so it isn't
+//! `mod`-included here and flagged as dead code on a normal library build.
+//!
+//! This file is compiled only into this crate (as `mod build_support` in
+//! `lib.rs`); `build.rs` does not include it and only emits calls to it.
+
+/// Looks up a guest's `(image_id, elf)` pair by name in a `GUESTS`-shaped
+/// table. Factored out of the generated `guest_by_name` so the lookup can
+/// be exercised directly against a literal table in tests.
+pub(crate) fn lookup_guest<'a>(
+    guests: &[(&str, [u32; 8], &'a [u8])],
+    name: &str,
+) -> Option<([u32; 8], &'a [u8])> {
+    guests
+        .iter()
+        .find(|(guest_name, _, _)| *guest_name == name)
+        .map(|(_, image_id, elf)| (*image_id, *elf))
+}
+
+/// Decompresses a zstd-compressed guest ELF. Factored out of the generated
+/// per-guest `<name>_elf()` accessors so the algorithm is exercised directly
+/// in tests instead of only inside a generated, guest-specific function.
+#[cfg(feature = "compressed-elf")]
+pub(crate) fn decompress_elf(compressed: &[u8]) -> Vec<u8> {
+    zstd::stream::decode_all(compressed).expect("embedded guest ELF is valid zstd")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_guest_finds_matching_name_and_misses_others() {
+        let guests: [(&str, [u32; 8], &[u8]); 2] = [
+            ("guest-a", [1; 8], &[0xaa, 0xbb]),
+            ("guest-b", [2; 8], &[0xcc, 0xdd]),
+        ];
+
+        assert_eq!(
+            lookup_guest(&guests, "guest-b"),
+            Some(([2; 8], &[0xcc, 0xdd][..]))
+        );
+        assert_eq!(lookup_guest(&guests, "missing"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "compressed-elf")]
+    fn decompress_elf_round_trips_zstd_encode_all() {
+        let original = b"not a real ELF, just some bytes to round-trip".to_vec();
+        let compressed = zstd::stream::encode_all(original.as_slice(), 19).unwrap();
+        assert_eq!(decompress_elf(&compressed), original);
+    }
+}
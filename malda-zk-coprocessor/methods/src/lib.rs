@@ -11,4 +11,29 @@
 //
 //
 //! Generated crate containing the image ID and ELF binary of the build guest.
+
+mod build_support;
+
 include!(concat!(env!("OUT_DIR"), "/methods.rs"));
+
+// Per-guest `EXPECTED_IMAGE_ID_<NAME>` constants, equal to the digests
+// pinned in `methods.lock`. `build.rs` fails the build before emitting them
+// if any `*_ID` constant above disagrees with the lock, unless
+// `MALDA_UPDATE_IMAGE_ID_LOCK` is set, in which case it rewrites the lock.
+//
+// (Plain `//` rather than `///`: a doc comment directly above an `include!`
+// macro invocation doesn't attach to anything it generates and just trips
+// `unused_doc_comments`.)
+include!(concat!(env!("OUT_DIR"), "/methods_lock.rs"));
+
+// `GUESTS` registry and `guest_by_name` lookup, so a host binary can
+// dispatch proofs to the correct ELF/image-ID pair by name instead of a
+// compile-time branch per method.
+include!(concat!(env!("OUT_DIR"), "/methods_registry.rs"));
+
+// Per-guest `<name>_elf()` accessors backed by a zstd-compressed copy of
+// the ELF, decompressed lazily and cached on first use. Opt in with the
+// `compressed-elf` feature; the plain `*_ELF` consts above are unaffected
+// and remain available when the feature is off.
+#[cfg(feature = "compressed-elf")]
+include!(concat!(env!("OUT_DIR"), "/methods_compressed.rs"));
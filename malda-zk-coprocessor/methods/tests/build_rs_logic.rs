@@ -0,0 +1,21 @@
+// Copyright (c) 2025 Merge Layers Inc.
+//
+// This source code is licensed under the Business Source License 1.1
+// (the "License"); you may not use this file except in compliance with the
+// License. You may obtain a copy of the License at
+//
+//     https://github.com/malda-protocol/malda-zk-coprocessor/blob/main/LICENSE-BSL
+//
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+//
+//! Exercises `build.rs`'s own lock-file helper logic by pulling in the same
+//! file `build.rs` uses, via `#[path]`. Kept out of `lib.rs`'s own `mod`
+//! tree (see `build_support_lock.rs`'s doc comment) so these helpers don't
+//! show up as dead code in a
+//! normal library build; this test binary is what actually runs the
+//! `#[cfg(test)]` tests living in `build_support_lock.rs`.
+
+#[path = "../build_support_lock.rs"]
+mod build_support_lock;